@@ -10,8 +10,11 @@ use std::{
     ffi::{CStr, CString},
     fmt::Display,
     mem::MaybeUninit,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
     ptr::{self, NonNull},
     slice,
+    sync::{atomic::Ordering, Arc, Weak},
     time::Duration,
 };
 
@@ -43,10 +46,37 @@ impl Default for MediaFormat {
 }
 
 impl MediaFormat {
-    fn as_ptr(&self) -> *mut ffi::AMediaFormat {
+    /// The codec's MIME type, e.g. `"video/avc"`.
+    pub const KEY_MIME: &'static str = "mime";
+    /// Video width, in pixels.
+    pub const KEY_WIDTH: &'static str = "width";
+    /// Video height, in pixels.
+    pub const KEY_HEIGHT: &'static str = "height";
+    /// Audio sample rate, in Hz.
+    pub const KEY_SAMPLE_RATE: &'static str = "sample-rate";
+    /// Number of audio channels.
+    pub const KEY_CHANNEL_COUNT: &'static str = "channel-count";
+    /// Bit rate, in bits per second.
+    pub const KEY_BIT_RATE: &'static str = "bitrate";
+    /// Video frame rate, in frames per second.
+    pub const KEY_FRAME_RATE: &'static str = "frame-rate";
+    /// Interval between key frames, in seconds.
+    pub const KEY_I_FRAME_INTERVAL: &'static str = "i-frame-interval";
+    /// Maximum size, in bytes, of a buffer of input data.
+    pub const KEY_MAX_INPUT_SIZE: &'static str = "max-input-size";
+    /// Duration of the content, in microseconds.
+    pub const KEY_DURATION: &'static str = "durationUs";
+    /// The surface color format, one of the `AMEDIACODEC_COLOR_FORMAT_*`/`COLOR_Format*` constants.
+    pub const KEY_COLOR_FORMAT: &'static str = "color-format";
+
+    pub(crate) fn as_ptr(&self) -> *mut ffi::AMediaFormat {
         self.inner.as_ptr()
     }
 
+    pub(crate) fn from_ptr(inner: NonNull<ffi::AMediaFormat>) -> Self {
+        Self { inner }
+    }
+
     pub fn new() -> Self {
         Self {
             inner: NonNull::new(unsafe { ffi::AMediaFormat_new() }).unwrap(),
@@ -202,6 +232,131 @@ impl MediaFormat {
         let name = CString::new(key).unwrap();
         unsafe { ffi::AMediaFormat_setSize(self.as_ptr(), name.as_ptr(), value) };
     }
+
+    /// Returns the codec-specific-data buffer at `index` (`"csd-0"`, `"csd-1"`, ...), e.g. the
+    /// SPS/PPS a decoder needs before it can process any frames.
+    pub fn csd(&self, index: usize) -> Option<&[u8]> {
+        self.buffer(&format!("csd-{index}"))
+    }
+
+    /// Sets the codec-specific-data buffer at `index` (`"csd-0"`, `"csd-1"`, ...).
+    pub fn set_csd(&self, index: usize, value: &[u8]) {
+        self.set_buffer(&format!("csd-{index}"), value);
+    }
+
+    /// Parses an ISO/IEC 14496-15 `AVCDecoderConfigurationRecord` (the `avcC` box content used by
+    /// MP4, and the `AVCDecoderConfigurationRecord` FLV's `avc_sequence_header` carries) and
+    /// installs its SPS/PPS as Annex-B, start-code-prefixed `csd-0`/`csd-1`, ready to pass to
+    /// [`MediaCodec::configure`].
+    ///
+    /// Returns an error if `avc_decoder_configuration_record` is truncated or declares a SPS/PPS
+    /// length that runs past the end of the buffer; this data typically comes straight out of a
+    /// container that may be malformed or corrupted.
+    pub fn set_avc_config(&self, avc_decoder_configuration_record: &[u8]) -> Result<()> {
+        let (sps, pps) = parse_avc_decoder_config(avc_decoder_configuration_record)?;
+        self.set_csd(0, &sps);
+        self.set_csd(1, &pps);
+        Ok(())
+    }
+}
+
+fn malformed_avc_config() -> MediaError {
+    MediaError::from_status(ffi::media_status_t(ffi::AMEDIA_ERROR_MALFORMED)).unwrap_err()
+}
+
+/// Reads a single length-prefixed (2-byte big-endian length) NAL unit starting at `*offset`,
+/// advances `*offset` past it, and appends it to `out` with an Annex-B start code prepended.
+fn read_length_prefixed_nal_unit(data: &[u8], offset: &mut usize, out: &mut Vec<u8>) -> Result<()> {
+    const START_CODE: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+
+    let len_bytes: [u8; 2] = data
+        .get(*offset..*offset + 2)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(malformed_avc_config)?;
+    let len = u16::from_be_bytes(len_bytes) as usize;
+    *offset += 2;
+
+    let nal_unit = data
+        .get(*offset..*offset + len)
+        .ok_or_else(malformed_avc_config)?;
+    out.extend_from_slice(&START_CODE);
+    out.extend_from_slice(nal_unit);
+    *offset += len;
+
+    Ok(())
+}
+
+/// Parses an ISO/IEC 14496-15 `AVCDecoderConfigurationRecord`, returning its SPS and PPS NAL
+/// units (in that order) with Annex-B start codes prepended. Pure and NDK-independent so it can
+/// be exercised directly; see [`MediaFormat::set_avc_config`] for the `MediaFormat`-facing API.
+fn parse_avc_decoder_config(data: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    // 5-byte header: configurationVersion, AVCProfileIndication, profile_compatibility,
+    // AVCLevelIndication, then a byte whose low 2 bits are lengthSizeMinusOne (unused here).
+    let mut offset = 5;
+
+    let num_sps = *data.get(offset).ok_or_else(malformed_avc_config)? & 0x1F;
+    offset += 1;
+    let mut sps = Vec::new();
+    for _ in 0..num_sps {
+        read_length_prefixed_nal_unit(data, &mut offset, &mut sps)?;
+    }
+
+    let num_pps = *data.get(offset).ok_or_else(malformed_avc_config)?;
+    offset += 1;
+    let mut pps = Vec::new();
+    for _ in 0..num_pps {
+        read_length_prefixed_nal_unit(data, &mut offset, &mut pps)?;
+    }
+
+    Ok((sps, pps))
+}
+
+#[cfg(test)]
+mod avc_config_tests {
+    use super::parse_avc_decoder_config;
+
+    fn sample_record() -> Vec<u8> {
+        let mut record = vec![1, 0x42, 0x00, 0x1e, 0xff];
+        record.push(0xe1); // reserved bits (0b111) | numSequenceParameterSets = 1
+        let sps = [0x67, 0x42, 0x00, 0x1e];
+        record.extend_from_slice(&(sps.len() as u16).to_be_bytes());
+        record.extend_from_slice(&sps);
+        record.push(1); // numPictureParameterSets
+        let pps = [0x68, 0xce, 0x3c, 0x80];
+        record.extend_from_slice(&(pps.len() as u16).to_be_bytes());
+        record.extend_from_slice(&pps);
+        record
+    }
+
+    #[test]
+    fn parses_sps_and_pps_into_annex_b() {
+        let (sps, pps) = parse_avc_decoder_config(&sample_record()).unwrap();
+        assert_eq!(sps, [0x00, 0x00, 0x00, 0x01, 0x67, 0x42, 0x00, 0x1e]);
+        assert_eq!(pps, [0x00, 0x00, 0x00, 0x01, 0x68, 0xce, 0x3c, 0x80]);
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert!(parse_avc_decoder_config(&[]).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_nal_length() {
+        let mut record = sample_record();
+        // Cut the record inside the SPS's 2-byte length prefix.
+        record.truncate(7);
+        assert!(parse_avc_decoder_config(&record).is_err());
+    }
+
+    #[test]
+    fn rejects_nal_length_past_end_of_buffer() {
+        let mut record = sample_record();
+        let last = record.len();
+        // Overwrite the PPS length with something far larger than the remaining bytes.
+        record[last - 6] = 0xff;
+        record[last - 5] = 0xff;
+        assert!(parse_avc_decoder_config(&record).is_err());
+    }
 }
 
 impl Drop for MediaFormat {
@@ -217,36 +372,51 @@ impl Drop for MediaFormat {
 #[derive(Debug)]
 pub struct MediaCodec {
     inner: NonNull<ffi::AMediaCodec>,
+    #[cfg(feature = "api-level-28")]
+    async_userdata: std::sync::atomic::AtomicPtr<c_void>,
 }
 
+// Plain `MediaCodec` is intentionally *not* `Send`/`Sync`: `AMediaCodec`'s synchronous API
+// (`configure`, `start`, `stop`, `flush`, `dequeue_input_buffer`, ...) is not documented to
+// tolerate concurrent calls from multiple threads. Only `AsyncCodecHandle`, the handle used by
+// asynchronous-mode notifications, opts into cross-thread sharing.
 impl MediaCodec {
     fn as_ptr(&self) -> *mut ffi::AMediaCodec {
         self.inner.as_ptr()
     }
 
+    #[cfg(not(feature = "api-level-28"))]
+    fn from_inner(inner: NonNull<ffi::AMediaCodec>) -> Self {
+        Self { inner }
+    }
+
+    #[cfg(feature = "api-level-28")]
+    fn from_inner(inner: NonNull<ffi::AMediaCodec>) -> Self {
+        Self {
+            inner,
+            async_userdata: std::sync::atomic::AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
     pub fn from_codec_name(name: &str) -> Option<Self> {
         let c_string = CString::new(name).unwrap();
-        Some(Self {
-            inner: NonNull::new(unsafe { ffi::AMediaCodec_createCodecByName(c_string.as_ptr()) })?,
-        })
+        let inner =
+            NonNull::new(unsafe { ffi::AMediaCodec_createCodecByName(c_string.as_ptr()) })?;
+        Some(Self::from_inner(inner))
     }
 
     pub fn from_decoder_type(mime_type: &str) -> Option<Self> {
         let c_string = CString::new(mime_type).unwrap();
-        Some(Self {
-            inner: NonNull::new(unsafe {
-                ffi::AMediaCodec_createDecoderByType(c_string.as_ptr())
-            })?,
-        })
+        let inner =
+            NonNull::new(unsafe { ffi::AMediaCodec_createDecoderByType(c_string.as_ptr()) })?;
+        Some(Self::from_inner(inner))
     }
 
     pub fn from_encoder_type(mime_type: &str) -> Option<Self> {
         let c_string = CString::new(mime_type).unwrap();
-        Some(Self {
-            inner: NonNull::new(unsafe {
-                ffi::AMediaCodec_createEncoderByType(c_string.as_ptr())
-            })?,
-        })
+        let inner =
+            NonNull::new(unsafe { ffi::AMediaCodec_createEncoderByType(c_string.as_ptr()) })?;
+        Some(Self::from_inner(inner))
     }
 
     pub fn configure(
@@ -386,11 +556,22 @@ impl MediaCodec {
         size: usize,
         time: u64,
         flags: u32,
+    ) -> Result<()> {
+        self.queue_input_buffer_index(buffer.index, offset, size, time, flags)
+    }
+
+    fn queue_input_buffer_index(
+        &self,
+        index: usize,
+        offset: usize,
+        size: usize,
+        time: u64,
+        flags: u32,
     ) -> Result<()> {
         let status = unsafe {
             ffi::AMediaCodec_queueInputBuffer(
                 self.as_ptr(),
-                buffer.index,
+                index,
                 offset as ffi::off_t,
                 size,
                 time,
@@ -401,8 +582,11 @@ impl MediaCodec {
     }
 
     pub fn release_output_buffer(&self, buffer: OutputBuffer, render: bool) -> Result<()> {
-        let status =
-            unsafe { ffi::AMediaCodec_releaseOutputBuffer(self.as_ptr(), buffer.index, render) };
+        self.release_output_buffer_index(buffer.index, render)
+    }
+
+    fn release_output_buffer_index(&self, index: usize, render: bool) -> Result<()> {
+        let status = unsafe { ffi::AMediaCodec_releaseOutputBuffer(self.as_ptr(), index, render) };
         MediaError::from_status(status)
     }
 
@@ -411,12 +595,40 @@ impl MediaCodec {
         buffer: OutputBuffer,
         timestamp_ns: i64,
     ) -> Result<()> {
+        self.release_output_buffer_at_time_index(buffer.index, timestamp_ns)
+    }
+
+    fn release_output_buffer_at_time_index(&self, index: usize, timestamp_ns: i64) -> Result<()> {
         let status = unsafe {
-            ffi::AMediaCodec_releaseOutputBufferAtTime(self.as_ptr(), buffer.index, timestamp_ns)
+            ffi::AMediaCodec_releaseOutputBufferAtTime(self.as_ptr(), index, timestamp_ns)
         };
         MediaError::from_status(status)
     }
 
+    fn input_buffer_mut_index(&self, index: usize) -> &mut [MaybeUninit<u8>] {
+        unsafe {
+            let mut out_size = 0;
+            let buffer_ptr = ffi::AMediaCodec_getInputBuffer(self.as_ptr(), index, &mut out_size);
+            assert!(
+                !buffer_ptr.is_null(),
+                "AMediaCodec_getInputBuffer returned NULL for index {index}"
+            );
+            slice::from_raw_parts_mut(buffer_ptr.cast(), out_size)
+        }
+    }
+
+    fn output_buffer_index(&self, index: usize, info: &ffi::AMediaCodecBufferInfo) -> &[u8] {
+        unsafe {
+            let mut _out_size = 0;
+            let buffer_ptr = ffi::AMediaCodec_getOutputBuffer(self.as_ptr(), index, &mut _out_size);
+            assert!(
+                !buffer_ptr.is_null(),
+                "AMediaCodec_getOutputBuffer returned NULL for index {index}"
+            );
+            slice::from_raw_parts(buffer_ptr.add(info.offset as usize), info.size as usize)
+        }
+    }
+
     #[cfg(feature = "api-level-26")]
     pub fn set_input_surface(&self, surface: &NativeWindow) -> Result<()> {
         let status =
@@ -442,6 +654,69 @@ impl MediaCodec {
         MediaError::from_status(status)
     }
 
+    /// Switches this codec into asynchronous mode, delivering [`AsyncNotification`]s to
+    /// `callback` from a thread owned by the codec instead of requiring callers to poll
+    /// [`Self::dequeue_input_buffer`]/[`Self::dequeue_output_buffer`].
+    ///
+    /// Because notifications (and the buffers they carry) can arrive on a thread other than the
+    /// one that called this method, `self` must be wrapped in an [`Arc`] first.
+    ///
+    /// Only one callback can ever be installed on a given codec: once the NDK may be dispatching
+    /// to it, there is no way to know when the last in-flight call into it has returned, so
+    /// swapping it out from under a concurrent notification would risk a use-after-free.
+    /// Returns an error if a callback is already installed. The installed callback (and the
+    /// `Weak<MediaCodec>` it holds) is dropped when the codec itself is dropped.
+    #[cfg(feature = "api-level-28")]
+    pub fn set_async_notify_callback<F>(self: &Arc<Self>, callback: F) -> Result<()>
+    where
+        F: FnMut(AsyncNotification) + Send + 'static,
+    {
+        let state = Box::new(AsyncCallbackState {
+            codec: Arc::downgrade(self),
+            callback: Box::new(callback),
+        });
+        let userdata = Box::into_raw(state).cast::<c_void>();
+
+        if self
+            .async_userdata
+            .compare_exchange(
+                ptr::null_mut(),
+                userdata,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_err()
+        {
+            free_async_callback_state(userdata);
+            return Err(
+                MediaError::from_status(ffi::media_status_t(ffi::AMEDIA_ERROR_INVALID_OPERATION))
+                    .unwrap_err(),
+            );
+        }
+
+        let callbacks = ffi::AMediaCodecOnAsyncNotifyCallback {
+            onAsyncInputAvailable: Some(on_async_input_available),
+            onAsyncOutputAvailable: Some(on_async_output_available),
+            onAsyncFormatChanged: Some(on_async_format_changed),
+            onAsyncError: Some(on_async_error),
+        };
+        let status =
+            unsafe { ffi::AMediaCodec_setAsyncNotifyCallback(self.as_ptr(), callbacks, userdata) };
+
+        match MediaError::from_status(status) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                // `AMediaCodec_setAsyncNotifyCallback` itself failed, so the NDK never started
+                // dispatching to `userdata`; it's safe to free immediately and let a later call
+                // retry.
+                self.async_userdata
+                    .store(ptr::null_mut(), Ordering::Release);
+                free_async_callback_state(userdata);
+                Err(err)
+            }
+        }
+    }
+
     pub fn start(&self) -> Result<()> {
         let status = unsafe { ffi::AMediaCodec_start(self.as_ptr()) };
         MediaError::from_status(status)
@@ -457,6 +732,11 @@ impl Drop for MediaCodec {
     fn drop(&mut self) {
         let status = unsafe { ffi::AMediaCodec_delete(self.as_ptr()) };
         MediaError::from_status(status).unwrap();
+
+        // `AMediaCodec_delete` has fully torn the codec down by this point, so the NDK can no
+        // longer be mid-dispatch to a trampoline holding this userdata; it's now safe to free.
+        #[cfg(feature = "api-level-28")]
+        free_async_callback_state(self.async_userdata.load(Ordering::Acquire));
     }
 }
 
@@ -468,17 +748,7 @@ pub struct InputBuffer<'a> {
 
 impl InputBuffer<'_> {
     pub fn buffer_mut(&mut self) -> &mut [MaybeUninit<u8>] {
-        unsafe {
-            let mut out_size = 0;
-            let buffer_ptr =
-                ffi::AMediaCodec_getInputBuffer(self.codec.as_ptr(), self.index, &mut out_size);
-            assert!(
-                !buffer_ptr.is_null(),
-                "AMediaCodec_getInputBuffer returned NULL for index {}",
-                self.index
-            );
-            slice::from_raw_parts_mut(buffer_ptr.cast(), out_size)
-        }
+        self.codec.input_buffer_mut_index(self.index)
     }
 }
 
@@ -497,20 +767,7 @@ pub struct OutputBuffer<'a> {
 
 impl OutputBuffer<'_> {
     pub fn buffer(&self) -> &[u8] {
-        unsafe {
-            let mut _out_size = 0;
-            let buffer_ptr =
-                ffi::AMediaCodec_getOutputBuffer(self.codec.as_ptr(), self.index, &mut _out_size);
-            assert!(
-                !buffer_ptr.is_null(),
-                "AMediaCodec_getOutputBuffer returned NULL for index {}",
-                self.index
-            );
-            slice::from_raw_parts(
-                buffer_ptr.add(self.info.offset as usize),
-                self.info.size as usize,
-            )
-        }
+        self.codec.output_buffer_index(self.index, &self.info)
     }
 
     #[cfg(feature = "api-level-28")]
@@ -538,3 +795,196 @@ pub enum DequeuedOutputBufferInfoResult<'a> {
     OutputFormatChanged,
     OutputBuffersChanged,
 }
+
+/// A thread-safe handle to a [`MediaCodec`] running in asynchronous mode.
+///
+/// Plain `MediaCodec` isn't `Send`/`Sync`, since its synchronous API isn't documented to tolerate
+/// concurrent calls from multiple threads. This handle is only ever constructed by the
+/// asynchronous-callback trampolines, which the NDK itself guarantees not to invoke concurrently
+/// with each other for the same codec, so narrowing the unsafe impl to this wrapper (rather than
+/// to `MediaCodec` itself) keeps that guarantee from leaking into ordinary synchronous use.
+#[derive(Debug, Clone)]
+struct AsyncCodecHandle(Arc<MediaCodec>);
+
+// SAFETY: see the trust boundary described above — only reachable via async notifications.
+unsafe impl Send for AsyncCodecHandle {}
+unsafe impl Sync for AsyncCodecHandle {}
+
+/// An input buffer handed to the callback installed via
+/// [`MediaCodec::set_async_notify_callback`].
+///
+/// Unlike [`InputBuffer`], this holds its own strong reference to the codec rather than
+/// borrowing it, since asynchronous notifications can outlive any particular stack frame.
+#[derive(Debug)]
+pub struct AsyncInputBuffer {
+    codec: AsyncCodecHandle,
+    index: usize,
+}
+
+impl AsyncInputBuffer {
+    pub fn buffer_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        self.codec.0.input_buffer_mut_index(self.index)
+    }
+
+    pub fn queue(self, offset: usize, size: usize, time: u64, flags: u32) -> Result<()> {
+        self.codec
+            .0
+            .queue_input_buffer_index(self.index, offset, size, time, flags)
+    }
+}
+
+/// An output buffer handed to the callback installed via
+/// [`MediaCodec::set_async_notify_callback`].
+///
+/// Unlike [`OutputBuffer`], this holds its own strong reference to the codec rather than
+/// borrowing it, since asynchronous notifications can outlive any particular stack frame.
+#[derive(Debug)]
+pub struct AsyncOutputBuffer {
+    codec: AsyncCodecHandle,
+    index: usize,
+    info: ffi::AMediaCodecBufferInfo,
+}
+
+impl AsyncOutputBuffer {
+    pub fn buffer(&self) -> &[u8] {
+        self.codec.0.output_buffer_index(self.index, &self.info)
+    }
+
+    #[cfg(feature = "api-level-28")]
+    pub fn format(&self) -> MediaFormat {
+        let inner = NonNull::new(unsafe {
+            ffi::AMediaCodec_getBufferFormat(self.codec.0.as_ptr(), self.index)
+        })
+        .expect("AMediaCodec_getBufferFormat returned NULL");
+        MediaFormat { inner }
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.info.flags
+    }
+
+    pub fn presentation_time_us(&self) -> i64 {
+        self.info.presentationTimeUs
+    }
+
+    pub fn release(self, render: bool) -> Result<()> {
+        self.codec
+            .0
+            .release_output_buffer_index(self.index, render)
+    }
+
+    pub fn release_at_time(self, timestamp_ns: i64) -> Result<()> {
+        self.codec
+            .0
+            .release_output_buffer_at_time_index(self.index, timestamp_ns)
+    }
+}
+
+/// A notification delivered to the callback installed via
+/// [`MediaCodec::set_async_notify_callback`].
+#[derive(Debug)]
+pub enum AsyncNotification {
+    InputBufferAvailable(AsyncInputBuffer),
+    OutputBufferAvailable(AsyncOutputBuffer),
+    /// The output format changed; see [`MediaCodec::output_format`].
+    FormatChanged(MediaFormat),
+    Error {
+        error: MediaError,
+        action_code: i32,
+        detail: String,
+    },
+}
+
+#[cfg(feature = "api-level-28")]
+struct AsyncCallbackState {
+    codec: Weak<MediaCodec>,
+    callback: Box<dyn FnMut(AsyncNotification) + Send>,
+}
+
+#[cfg(feature = "api-level-28")]
+fn free_async_callback_state(userdata: *mut c_void) {
+    if !userdata.is_null() {
+        drop(unsafe { Box::from_raw(userdata.cast::<AsyncCallbackState>()) });
+    }
+}
+
+/// Upgrades `userdata` back into an [`AsyncCallbackState`] and runs `f` with it and a strong
+/// reference to the codec, catching panics so that Rust unwinding never crosses the FFI boundary
+/// back into the NDK.
+#[cfg(feature = "api-level-28")]
+fn with_async_callback_state(userdata: *mut c_void, f: impl FnOnce(&mut AsyncCallbackState, &Arc<MediaCodec>)) {
+    let state = unsafe { &mut *userdata.cast::<AsyncCallbackState>() };
+    let Some(codec) = state.codec.upgrade() else {
+        return;
+    };
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| f(state, &codec)));
+}
+
+#[cfg(feature = "api-level-28")]
+extern "C" fn on_async_input_available(
+    _codec: *mut ffi::AMediaCodec,
+    userdata: *mut c_void,
+    index: i32,
+) {
+    with_async_callback_state(userdata, |state, codec| {
+        (state.callback)(AsyncNotification::InputBufferAvailable(AsyncInputBuffer {
+            codec: AsyncCodecHandle(codec.clone()),
+            index: index as usize,
+        }));
+    });
+}
+
+#[cfg(feature = "api-level-28")]
+extern "C" fn on_async_output_available(
+    _codec: *mut ffi::AMediaCodec,
+    userdata: *mut c_void,
+    index: i32,
+    buffer_info: *mut ffi::AMediaCodecBufferInfo,
+) {
+    with_async_callback_state(userdata, |state, codec| {
+        let info = unsafe { *buffer_info };
+        (state.callback)(AsyncNotification::OutputBufferAvailable(AsyncOutputBuffer {
+            codec: AsyncCodecHandle(codec.clone()),
+            index: index as usize,
+            info,
+        }));
+    });
+}
+
+#[cfg(feature = "api-level-28")]
+extern "C" fn on_async_format_changed(
+    _codec: *mut ffi::AMediaCodec,
+    userdata: *mut c_void,
+    format: *mut ffi::AMediaFormat,
+) {
+    with_async_callback_state(userdata, |state, _codec| {
+        let Some(inner) = NonNull::new(format) else {
+            return;
+        };
+        // Follows the same ownership rules as `MediaCodec::output_format`: the caller is
+        // responsible for deleting the returned `MediaFormat`.
+        (state.callback)(AsyncNotification::FormatChanged(MediaFormat { inner }));
+    });
+}
+
+#[cfg(feature = "api-level-28")]
+extern "C" fn on_async_error(
+    _codec: *mut ffi::AMediaCodec,
+    userdata: *mut c_void,
+    error: ffi::media_status_t,
+    action_code: i32,
+    detail: *const std::os::raw::c_char,
+) {
+    with_async_callback_state(userdata, |state, _codec| {
+        let detail = if detail.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(detail) }.to_string_lossy().into_owned()
+        };
+        (state.callback)(AsyncNotification::Error {
+            error: MediaError::from_status(error).unwrap_err(),
+            action_code,
+            detail,
+        });
+    });
+}
@@ -0,0 +1,89 @@
+//! Support for feeding [`MediaExtractor`](super::media_extractor::MediaExtractor) from an
+//! arbitrary Rust byte source, via [`AMediaDataSource`].
+//!
+//! [`AMediaDataSource`]: https://developer.android.com/ndk/reference/group/media#amediadatasource
+
+use std::{
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    ptr::NonNull,
+    slice,
+};
+
+/// A Rust-implemented source of compressed media bytes, for use with
+/// [`MediaExtractor::set_data_source`](super::media_extractor::MediaExtractor::set_data_source)
+/// instead of a plain file descriptor — e.g. a network stream, an in-memory buffer, or a
+/// decrypting reader.
+pub trait MediaDataSource: Send {
+    /// Reads up to `buf.len()` bytes starting at `offset`, returning the number of bytes read,
+    /// or `-1` at end of stream or on error. The value is passed to the NDK unchanged.
+    fn read_at(&mut self, offset: i64, buf: &mut [u8]) -> i64;
+
+    /// The total size of the source, or a negative value if unknown.
+    fn size(&self) -> i64;
+
+    /// Called once the extractor is done with this source.
+    fn close(&mut self);
+}
+
+/// Owns the [`AMediaDataSource *`] and the boxed [`MediaDataSource`] behind it, deleting both
+/// together.
+///
+/// [`AMediaDataSource *`]: https://developer.android.com/ndk/reference/group/media#amediadatasource
+#[derive(Debug)]
+pub(crate) struct NativeDataSource {
+    inner: NonNull<ffi::AMediaDataSource>,
+    userdata: *mut c_void,
+}
+
+impl NativeDataSource {
+    pub(crate) fn new(source: Box<dyn MediaDataSource>) -> Self {
+        let userdata = Box::into_raw(Box::new(source)).cast::<c_void>();
+        let inner = NonNull::new(unsafe { ffi::AMediaDataSource_new() })
+            .expect("AMediaDataSource_new returned NULL");
+        unsafe {
+            ffi::AMediaDataSource_setUserdata(inner.as_ptr(), userdata);
+            ffi::AMediaDataSource_setReadAt(inner.as_ptr(), Some(read_at));
+            ffi::AMediaDataSource_setGetSize(inner.as_ptr(), Some(get_size));
+            ffi::AMediaDataSource_setClose(inner.as_ptr(), Some(close));
+        }
+        Self { inner, userdata }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut ffi::AMediaDataSource {
+        self.inner.as_ptr()
+    }
+}
+
+impl Drop for NativeDataSource {
+    fn drop(&mut self) {
+        unsafe { ffi::AMediaDataSource_delete(self.as_ptr()) };
+        drop(unsafe { Box::from_raw(self.userdata.cast::<Box<dyn MediaDataSource>>()) });
+    }
+}
+
+/// Reconstructs the boxed [`MediaDataSource`] behind `userdata`, runs `f` with it, and turns a
+/// panic into `on_unwind` instead of letting it cross back into the NDK.
+fn with_source<T>(
+    userdata: *mut c_void,
+    on_unwind: T,
+    f: impl FnOnce(&mut Box<dyn MediaDataSource>) -> T + panic::UnwindSafe,
+) -> T {
+    let source = unsafe { &mut *userdata.cast::<Box<dyn MediaDataSource>>() };
+    panic::catch_unwind(AssertUnwindSafe(|| f(source))).unwrap_or(on_unwind)
+}
+
+extern "C" fn read_at(userdata: *mut c_void, offset: i64, buffer: *mut c_void, size: usize) -> isize {
+    with_source(userdata, -1, |source| {
+        let buf = unsafe { slice::from_raw_parts_mut(buffer.cast::<u8>(), size) };
+        source.read_at(offset, buf) as isize
+    })
+}
+
+extern "C" fn get_size(userdata: *mut c_void) -> isize {
+    with_source(userdata, -1, |source| source.size() as isize)
+}
+
+extern "C" fn close(userdata: *mut c_void) {
+    with_source(userdata, (), |source| source.close())
+}
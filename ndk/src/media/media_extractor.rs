@@ -0,0 +1,140 @@
+//! Bindings for [`AMediaExtractor`]
+//!
+//! [`AMediaExtractor`]: https://developer.android.com/ndk/reference/group/media#amediaextractor
+
+use super::media_codec::MediaFormat;
+use super::media_data_source::{MediaDataSource, NativeDataSource};
+use crate::media_error::{MediaError, Result};
+use std::{convert::TryInto, mem::MaybeUninit, os::unix::io::RawFd, ptr::NonNull};
+
+/// A native [`AMediaExtractor *`], demuxing compressed samples out of a container so they can be
+/// fed to [`MediaCodec`](super::media_codec::MediaCodec).
+///
+/// [`AMediaExtractor *`]: https://developer.android.com/ndk/reference/group/media#amediaextractor
+#[derive(Debug)]
+pub struct MediaExtractor {
+    inner: NonNull<ffi::AMediaExtractor>,
+    #[cfg(feature = "api-level-28")]
+    data_source: Option<NativeDataSource>,
+}
+
+impl Default for MediaExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediaExtractor {
+    fn as_ptr(&self) -> *mut ffi::AMediaExtractor {
+        self.inner.as_ptr()
+    }
+
+    pub fn new() -> Self {
+        let inner = NonNull::new(unsafe { ffi::AMediaExtractor_new() })
+            .expect("AMediaExtractor_new returned NULL");
+        Self {
+            inner,
+            #[cfg(feature = "api-level-28")]
+            data_source: None,
+        }
+    }
+
+    /// Reads compressed samples from a custom Rust-implemented byte source instead of a file
+    /// descriptor, e.g. a network stream, an in-memory buffer, or a decrypting reader.
+    ///
+    /// Replaces any data source previously set on this extractor.
+    #[cfg(feature = "api-level-28")]
+    pub fn set_data_source(&mut self, source: impl MediaDataSource + 'static) -> Result<()> {
+        let native = NativeDataSource::new(Box::new(source));
+        let status =
+            unsafe { ffi::AMediaExtractor_setDataSourceCustom(self.as_ptr(), native.as_ptr()) };
+        MediaError::from_status(status)?;
+        self.data_source = Some(native);
+        Ok(())
+    }
+
+    /// Sets `fd` as the data source, reading `length` bytes starting at `offset`.
+    ///
+    /// The extractor does not take ownership of `fd` and does not close it on drop.
+    pub fn set_data_source_fd(&self, fd: RawFd, offset: u64, length: u64) -> Result<()> {
+        let status = unsafe {
+            ffi::AMediaExtractor_setDataSourceFd(self.as_ptr(), fd, offset as i64, length as i64)
+        };
+        MediaError::from_status(status)
+    }
+
+    pub fn track_count(&self) -> usize {
+        unsafe { ffi::AMediaExtractor_getTrackCount(self.as_ptr()) }
+    }
+
+    /// Returns the format of track `idx`, suitable for passing to
+    /// [`MediaCodec::configure`](super::media_codec::MediaCodec::configure).
+    pub fn track_format(&self, idx: usize) -> MediaFormat {
+        let inner = NonNull::new(unsafe { ffi::AMediaExtractor_getTrackFormat(self.as_ptr(), idx) })
+            .expect("AMediaExtractor_getTrackFormat returned NULL");
+        MediaFormat::from_ptr(inner)
+    }
+
+    /// Selects track `idx` for inclusion in the samples returned by
+    /// [`Self::read_sample_data`]/[`Self::advance`].
+    pub fn select_track(&self, idx: usize) -> Result<()> {
+        let status = unsafe { ffi::AMediaExtractor_selectTrack(self.as_ptr(), idx) };
+        MediaError::from_status(status)
+    }
+
+    pub fn unselect_track(&self, idx: usize) -> Result<()> {
+        let status = unsafe { ffi::AMediaExtractor_unselectTrack(self.as_ptr(), idx) };
+        MediaError::from_status(status)
+    }
+
+    /// Copies the current sample into `buffer`, returning the number of bytes written, or `None`
+    /// at end of stream.
+    pub fn read_sample_data(&self, buffer: &mut [MaybeUninit<u8>]) -> Option<usize> {
+        let result = unsafe {
+            ffi::AMediaExtractor_readSampleData(
+                self.as_ptr(),
+                buffer.as_mut_ptr().cast(),
+                buffer.len().try_into().expect("buffer too large for extractor"),
+            )
+        };
+        if result < 0 {
+            None
+        } else {
+            Some(result as usize)
+        }
+    }
+
+    /// The presentation timestamp, in microseconds, of the current sample, or `None` if there
+    /// isn't one (`AMediaExtractor_getSampleTime` returns `-1`).
+    pub fn sample_time(&self) -> Option<i64> {
+        let time = unsafe { ffi::AMediaExtractor_getSampleTime(self.as_ptr()) };
+        if time < 0 {
+            None
+        } else {
+            Some(time)
+        }
+    }
+
+    /// The `AMEDIAEXTRACTOR_SAMPLE_FLAG_*` flags of the current sample, or `None` if there isn't
+    /// one (`AMediaExtractor_getSampleFlags` returns `-1`).
+    pub fn sample_flags(&self) -> Option<u32> {
+        let flags = unsafe { ffi::AMediaExtractor_getSampleFlags(self.as_ptr()) };
+        if flags < 0 {
+            None
+        } else {
+            Some(flags as u32)
+        }
+    }
+
+    /// Advances to the next sample, returning `false` at end of stream.
+    pub fn advance(&self) -> bool {
+        unsafe { ffi::AMediaExtractor_advance(self.as_ptr()) }
+    }
+}
+
+impl Drop for MediaExtractor {
+    fn drop(&mut self) {
+        let status = unsafe { ffi::AMediaExtractor_delete(self.as_ptr()) };
+        MediaError::from_status(status).unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+//! Bindings for [`AMediaMuxer`]
+//!
+//! [`AMediaMuxer`]: https://developer.android.com/ndk/reference/group/media#amediamuxer
+
+use super::media_codec::{MediaFormat, OutputBuffer};
+use crate::media_error::{MediaError, Result};
+use std::{convert::TryInto, os::unix::io::RawFd, ptr::NonNull};
+
+/// The container format an [`AMediaMuxer`] writes.
+///
+/// [`AMediaMuxer`]: https://developer.android.com/ndk/reference/group/media#amediamuxer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Mp4,
+    WebM,
+    ThreeGpp,
+    #[cfg(feature = "api-level-29")]
+    Heif,
+    #[cfg(feature = "api-level-29")]
+    Ogg,
+    /// Fragmented (CMAF-style) MP4: samples are flushed in fragments rather than buffered for a
+    /// single trailing `moov` atom.
+    #[cfg(feature = "api-level-33")]
+    FragmentedMp4,
+}
+
+impl OutputFormat {
+    fn as_raw(self) -> ffi::OutputFormat {
+        match self {
+            Self::Mp4 => ffi::AMEDIAMUXER_OUTPUT_FORMAT_MPEG_4,
+            Self::WebM => ffi::AMEDIAMUXER_OUTPUT_FORMAT_WEBM,
+            Self::ThreeGpp => ffi::AMEDIAMUXER_OUTPUT_FORMAT_3GPP,
+            #[cfg(feature = "api-level-29")]
+            Self::Heif => ffi::AMEDIAMUXER_OUTPUT_FORMAT_HEIF,
+            #[cfg(feature = "api-level-29")]
+            Self::Ogg => ffi::AMEDIAMUXER_OUTPUT_FORMAT_OGG,
+            #[cfg(feature = "api-level-33")]
+            Self::FragmentedMp4 => ffi::AMEDIAMUXER_OUTPUT_FORMAT_FRAGMENTED_MPEG_4,
+        }
+    }
+}
+
+/// A native [`AMediaMuxer *`], writing encoded samples into a container file.
+///
+/// [`AMediaMuxer *`]: https://developer.android.com/ndk/reference/group/media#amediamuxer
+#[derive(Debug)]
+pub struct MediaMuxer {
+    inner: NonNull<ffi::AMediaMuxer>,
+}
+
+impl MediaMuxer {
+    fn as_ptr(&self) -> *mut ffi::AMediaMuxer {
+        self.inner.as_ptr()
+    }
+
+    /// Creates a muxer that writes `format`-encoded output to `fd`.
+    ///
+    /// `fd` must be a seekable, writable file descriptor; the muxer does not take ownership of
+    /// it and does not close it on drop.
+    pub fn new(fd: RawFd, format: OutputFormat) -> Option<Self> {
+        Some(Self {
+            inner: NonNull::new(unsafe { ffi::AMediaMuxer_new(fd, format.as_raw()) })?,
+        })
+    }
+
+    /// Adds `format` as a new track and returns its track index, for use with
+    /// [`Self::write_sample_data`].
+    ///
+    /// Must be called before [`Self::start`].
+    pub fn add_track(&self, format: &MediaFormat) -> Result<usize> {
+        let result = unsafe { ffi::AMediaMuxer_addTrack(self.as_ptr(), format.as_ptr()) };
+        if result >= 0 {
+            Ok(result as usize)
+        } else {
+            Err(MediaError::from_status(ffi::media_status_t(result as _)).unwrap_err())
+        }
+    }
+
+    /// Starts the muxer; must be called after all tracks have been added and before any sample
+    /// is written.
+    pub fn start(&self) -> Result<()> {
+        let status = unsafe { ffi::AMediaMuxer_start(self.as_ptr()) };
+        MediaError::from_status(status)
+    }
+
+    /// Stops the muxer and flushes the container's trailer to the underlying file descriptor.
+    pub fn stop(&self) -> Result<()> {
+        let status = unsafe { ffi::AMediaMuxer_stop(self.as_ptr()) };
+        MediaError::from_status(status)
+    }
+
+    /// Writes an encoded `MediaCodec` output buffer to `track_idx`.
+    ///
+    /// The buffer's offset, size, presentation timestamp and flags are taken directly from
+    /// `buffer`, matching the sample `MediaCodec` produced.
+    pub fn write_sample_data(&self, track_idx: usize, buffer: &OutputBuffer) -> Result<()> {
+        let data = buffer.buffer();
+        let info = ffi::AMediaCodecBufferInfo {
+            offset: 0,
+            size: data.len().try_into().expect("buffer too large for muxer"),
+            presentationTimeUs: buffer.presentation_time_us(),
+            flags: buffer.flags(),
+        };
+        let status = unsafe {
+            ffi::AMediaMuxer_writeSampleData(self.as_ptr(), track_idx, data.as_ptr(), &info)
+        };
+        MediaError::from_status(status)
+    }
+
+    /// Sets the orientation hint, in degrees, applied to the output video track(s).
+    ///
+    /// `degrees` must be a multiple of 90. Must be called before [`Self::start`].
+    #[cfg(feature = "api-level-28")]
+    pub fn set_orientation_hint(&self, degrees: i32) -> Result<()> {
+        let status = unsafe { ffi::AMediaMuxer_setOrientationHint(self.as_ptr(), degrees) };
+        MediaError::from_status(status)
+    }
+}
+
+impl Drop for MediaMuxer {
+    fn drop(&mut self) {
+        let status = unsafe { ffi::AMediaMuxer_delete(self.as_ptr()) };
+        MediaError::from_status(status).unwrap();
+    }
+}
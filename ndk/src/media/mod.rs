@@ -0,0 +1,4 @@
+pub mod media_codec;
+pub mod media_data_source;
+pub mod media_extractor;
+pub mod media_muxer;